@@ -1,15 +1,34 @@
 use crate::rim::{Frame, Window};
+use ropey::Rope;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::Path;
 
+/// column width a '\t' expands to, rounded up to the next multiple of this
+const TAB_STOP: usize = 4;
+
+/// A single reversible mutation to `Buffer::data`, enough to invert the operation
+#[derive(Debug, Clone)]
+enum Edit {
+    Insert { row: usize, col: usize, c: char },
+    Delete { row: usize, col: usize, c: char },
+    Split { row: usize, col: usize },
+    Join { row: usize, col: usize },
+}
+
 pub struct Buffer {
-    data: Vec<Vec<char>>,
+    data: Rope,
     name: String,   /* path to file to edit */
     cur_col: usize, /* cursor pointing to col/char in data */
     cur_row: usize, /* cursor pointing to row/line in data */
+    col_want: usize, /* desired column for vertical motion, sticky across short lines */
     buf_row: usize, /* starting row to print */
     buf_col: usize, /* starting col to print */
+    undo_stack: Vec<Vec<Edit>>, /* groups of edits, most recent last */
+    redo_stack: Vec<Vec<Edit>>, /* groups undone, most recent last */
+    show_line_numbers: bool, /* whether print() draws the line-number gutter */
+    dirty: usize, /* number of edits since the last save */
+    last_query: String, /* most recently confirmed search, targeted by search_next/search_prev */
 }
 
 impl Buffer {
@@ -21,9 +40,9 @@ impl Buffer {
         };
 
         let data = if Path::new(&name).is_file() {
-            read_buffer(&name)
+            Rope::from_str(&fs::read_to_string(&name).unwrap())
         } else {
-            empty_buffer()
+            Rope::new()
         };
 
         Self {
@@ -31,27 +50,41 @@ impl Buffer {
             name,
             cur_col: 0,
             cur_row: 0,
+            col_want: 0,
             buf_row: 0,
             buf_col: 0,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            show_line_numbers: false,
+            dirty: 0,
+            last_query: String::new(),
         }
     }
 
+    /// toggles the line-number gutter drawn by `print`
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+    }
+
+    /// whether the buffer has edits since the last save
+    pub fn is_dirty(&self) -> bool {
+        self.dirty > 0
+    }
+
+    /// one-line summary shown on the status line: filename, modified marker, cursor position
+    pub fn status_line(&self) -> String {
+        let name = if self.name.is_empty() {
+            "[No Name]"
+        } else {
+            &self.name
+        };
+        let modified = if self.is_dirty() { " [+]" } else { "" };
+
+        format!("{name}{modified} — {}:{}", self.cur_row + 1, self.cur_col + 1)
+    }
+
     pub fn save(&mut self) {
-        let last_line_index = self.data.len() - 1;
-        let byte_string: String = self
-            .data
-            .iter()
-            .enumerate()
-            .fold(vec![], |mut acc, (i, line)| {
-                acc.extend_from_slice(line.as_slice());
-                acc.pop(); // removes delimeter at end of each line in self.data
-                if i != last_line_index {
-                    acc.push('\n'); // nl at the end of every line except last line
-                }
-                return acc;
-            })
-            .into_iter()
-            .collect();
+        let byte_string = self.data.to_string();
 
         let save_to_file = |mut f: File| {
             f.write(byte_string.as_bytes()).unwrap();
@@ -62,36 +95,53 @@ impl Buffer {
             Ok(f) => save_to_file(f),
             Err(e) => eprintln!("Err creating file: {e}"),
         }
+
+        self.dirty = 0;
     }
 
     pub fn print(&mut self, window: &mut Window, frame: Frame) -> std::io::Result<()> {
-        // Important to shift buffer position so cursor remain inside frame
-        self.confine_frame(frame);
+        let gutter_width = self.gutter_width();
+        let text_start_col = frame.start_col + gutter_width;
 
-        let mut data_row = self.buf_col; /* row pointing in self.data */
+        // Important to shift buffer position so cursor remain inside frame;
+        // confine against the text area only, so the gutter isn't counted
+        // as scrollable width
+        let mut text_frame = frame;
+        text_frame.start_col = text_start_col;
+        self.confine_frame(text_frame);
+
+        let mut data_row = self.buf_col; /* row pointing in self.data, not the on-screen row */
         let mut row = frame.start_row; /* row pointing in window frame */
         //
         while row < frame.end_row {
             //
-            if data_row < self.data.len() {
-                // print row
+            if data_row < self.num_lines() {
+                if gutter_width > 0 {
+                    self.draw_line_number(window, row, frame.start_col, gutter_width, data_row);
+                }
+
+                // print row, drawing from the tab-expanded render line
+                let rendered = self.render_line(data_row);
                 let mut data_col = self.buf_row;
-                let mut col = frame.start_col;
+                let mut col = text_start_col;
 
                 while col < frame.end_col {
-                    if data_col < self.data[data_row].len() {
-                        window[row as usize][col as usize] = self.data[data_row][data_col];
-                    } else {
-                        window[row as usize][col as usize] = ' ';
-                    }
+                    window[row as usize][col as usize] =
+                        rendered.get(data_col).copied().unwrap_or(' ');
                     data_col += 1;
                     col += 1;
                 }
             } else {
-                // line is empty hence print a tilde at start
-                window[row as usize][frame.start_col as usize] = '~';
+                // line is empty hence print a tilde at start, after the (blank) gutter
+                let mut col = frame.start_col;
+                while col < text_start_col {
+                    window[row as usize][col as usize] = ' ';
+                    col += 1;
+                }
+
+                window[row as usize][text_start_col as usize] = '~';
                 // and the rest of line is empty, important erase previous garbage chars
-                let mut col = frame.start_col + 1;
+                let mut col = text_start_col + 1;
                 while col < frame.end_col {
                     window[row as usize][col as usize] = ' ';
                     col += 1;
@@ -105,13 +155,9 @@ impl Buffer {
     }
 
     pub fn insert_nl(&mut self) {
-        // make newline by copying all element of current line starting at cursor x
-        let newline = (self.data[self.cur_row][self.cur_col..]).to_vec();
-        self.data.insert(self.cur_row + 1, newline);
-
-        // trim the current line and push delimenter at end
-        self.data[self.cur_row].truncate(self.cur_col);
-        self.data[self.cur_row].push('\0');
+        let (row, col) = (self.cur_row, self.cur_col);
+        self.do_split(row, col);
+        self.push_edit(Edit::Split { row, col });
 
         // move the cursor to point first char of next line
         self.move_down();
@@ -123,34 +169,289 @@ impl Buffer {
             return; // no line above to join current line
         }
 
-        // remove delemeter of line above current line
-        self.data[self.cur_row - 1].pop().unwrap();
-        let above_line_len = self.data[self.cur_row - 1].len();
-        // join current line to above line and remove current line
-        let mut line_to_join = self.data.remove(self.cur_row);
-        self.data[self.cur_row - 1].append(&mut line_to_join);
+        let row = self.cur_row;
+        let above_line_len = self.do_join(row);
+        self.push_edit(Edit::Join {
+            row: row - 1,
+            col: above_line_len,
+        });
 
         // reset cursor position
         self.move_up();
-        self.cur_col = above_line_len;
+        self.set_cur_col(above_line_len);
     }
 
     /// insert char at cursor and shifts the cursor right
     pub fn insert_char(&mut self, c: char) {
-        self.data[self.cur_row].insert(self.cur_col, c);
+        let (row, col) = (self.cur_row, self.cur_col);
+        let idx = self.char_idx(row, col);
+        self.data.insert_char(idx, c);
+        self.push_edit(Edit::Insert { row, col, c });
         self.move_right();
     }
 
     /// delete char just behind the cursor and shifts the cursor left
     pub fn delete_char(&mut self) {
         if self.cur_col > 0 {
-            self.data[self.cur_row].remove(self.cur_col - 1);
+            let (row, col) = (self.cur_row, self.cur_col - 1);
+            let idx = self.char_idx(row, col);
+            let c = self.data.char(idx);
+            self.data.remove(idx..idx + 1);
+            self.push_edit(Edit::Delete { row, col, c });
             self.move_left();
         } else {
             // join current line to above and delete current line
             self.join_line()
         }
     }
+
+    /// delete the char under the cursor, leaving the cursor in place (vim's `x`)
+    pub fn delete_under_cursor(&mut self) {
+        if self.cur_col < self.line_len(self.cur_row) - 1 {
+            let (row, col) = (self.cur_row, self.cur_col);
+            let idx = self.char_idx(row, col);
+            let c = self.data.char(idx);
+            self.data.remove(idx..idx + 1);
+            self.push_edit(Edit::Delete { row, col, c });
+            // clamp cur_col to the now-shorter line; unlike reset_x this
+            // doesn't touch col_want, which tracks vertical motion, not a delete
+            self.cur_col = self.cur_col.min(self.line_len(self.cur_row) - 1);
+        }
+    }
+
+    /// open a new empty line below the current one and move the cursor onto it (vim's `o`)
+    pub fn open_line_below(&mut self) {
+        self.move_end_of_line();
+        self.insert_nl();
+    }
+}
+
+/// Undo/redo
+impl Buffer {
+    /// splits the line at `row` at `col`, moving the remainder onto a new line below
+    fn do_split(&mut self, row: usize, col: usize) {
+        let idx = self.char_idx(row, col);
+        self.data.insert_char(idx, '\n');
+    }
+
+    /// merges the line at `row` into the line above it, returning the column they were joined at
+    fn do_join(&mut self, row: usize) -> usize {
+        let above_line_len = self.line_content_len(row - 1);
+        let idx = self.data.line_to_char(row - 1) + above_line_len;
+        self.data.remove(idx..idx + 1);
+        above_line_len
+    }
+
+    /// records an edit, coalescing consecutive single-char inserts so a typed word undoes at once
+    fn push_edit(&mut self, edit: Edit) {
+        self.dirty += 1;
+        self.redo_stack.clear();
+
+        let coalesces = match (self.undo_stack.last(), &edit) {
+            (Some(group), Edit::Insert { row, col, .. }) => matches!(
+                group.last(),
+                Some(Edit::Insert { row: r, col: c, .. }) if r == row && c + 1 == *col
+            ),
+            _ => false,
+        };
+
+        if coalesces {
+            self.undo_stack.last_mut().unwrap().push(edit);
+        } else {
+            self.undo_stack.push(vec![edit]);
+        }
+    }
+
+    /// applies the inverse of a single edit, restoring data and moving the cursor to
+    /// the edit's position (just after the char, when a deleted char is re-inserted)
+    fn invert(&mut self, edit: &Edit) {
+        self.dirty += 1;
+        match *edit {
+            Edit::Insert { row, col, .. } => {
+                let idx = self.char_idx(row, col);
+                self.data.remove(idx..idx + 1);
+                self.cur_row = row;
+                self.set_cur_col(col);
+            }
+            Edit::Delete { row, col, c } => {
+                let idx = self.char_idx(row, col);
+                self.data.insert_char(idx, c);
+                self.cur_row = row;
+                self.set_cur_col(col + 1);
+            }
+            Edit::Split { row, col } => {
+                self.do_join(row + 1);
+                self.cur_row = row;
+                self.set_cur_col(col);
+            }
+            Edit::Join { row, col } => {
+                self.do_split(row, col);
+                self.cur_row = row + 1;
+                self.set_cur_col(0);
+            }
+        }
+    }
+
+    /// re-applies a single edit going forward, restoring cursor position
+    fn apply(&mut self, edit: &Edit) {
+        self.dirty += 1;
+        match *edit {
+            Edit::Insert { row, col, c } => {
+                let idx = self.char_idx(row, col);
+                self.data.insert_char(idx, c);
+                self.cur_row = row;
+                self.set_cur_col(col + 1);
+            }
+            Edit::Delete { row, col, .. } => {
+                let idx = self.char_idx(row, col);
+                self.data.remove(idx..idx + 1);
+                self.cur_row = row;
+                self.set_cur_col(col);
+            }
+            Edit::Split { row, col } => {
+                self.do_split(row, col);
+                self.cur_row = row + 1;
+                self.set_cur_col(0);
+            }
+            Edit::Join { row, col } => {
+                self.do_join(row + 1);
+                self.cur_row = row;
+                self.set_cur_col(col);
+            }
+        }
+    }
+
+    /// undoes the last group of edits
+    pub fn undo(&mut self) {
+        if let Some(group) = self.undo_stack.pop() {
+            for edit in group.iter().rev() {
+                self.invert(edit);
+            }
+            self.redo_stack.push(group);
+        }
+    }
+
+    /// redoes the last undone group of edits
+    pub fn redo(&mut self) {
+        if let Some(group) = self.redo_stack.pop() {
+            for edit in group.iter() {
+                self.apply(edit);
+            }
+            self.undo_stack.push(group);
+        }
+    }
+}
+
+/// Rope indexing
+impl Buffer {
+    /// number of lines in the document
+    fn num_lines(&self) -> usize {
+        self.data.len_lines()
+    }
+
+    /// length of `row`'s real content, excluding its terminating '\n' if it has one
+    fn line_content_len(&self, row: usize) -> usize {
+        let line = self.data.line(row);
+        let len = line.len_chars();
+        if len > 0 && line.char(len - 1) == '\n' {
+            len - 1
+        } else {
+            len
+        }
+    }
+
+    /// number of addressable columns on `row`: its content plus one trailing
+    /// delimiter slot, mirroring the old '\0'-terminated `Vec<char>` rows
+    fn line_len(&self, row: usize) -> usize {
+        self.line_content_len(row) + 1
+    }
+
+    /// char at (row, col); the delimiter slot at the end of every row reads as '\0',
+    /// same sentinel the old `Vec<char>` rows stored there
+    fn char_at(&self, row: usize, col: usize) -> char {
+        if col < self.line_content_len(row) {
+            self.data.line(row).char(col)
+        } else {
+            '\0'
+        }
+    }
+
+    /// absolute rope char index for (row, col)
+    fn char_idx(&self, row: usize, col: usize) -> usize {
+        self.data.line_to_char(row) + col
+    }
+}
+
+/// Render
+impl Buffer {
+    /// `row`'s content with tabs expanded to the next `TAB_STOP` boundary, as kilo does
+    fn render_line(&self, row: usize) -> Vec<char> {
+        let line = self.data.line(row);
+        let mut rendered = Vec::with_capacity(self.line_content_len(row));
+
+        for col in 0..self.line_content_len(row) {
+            let c = line.char(col);
+            if c == '\t' {
+                let width = TAB_STOP - (rendered.len() % TAB_STOP);
+                rendered.extend(std::iter::repeat_n(' ', width));
+            } else {
+                rendered.push(c);
+            }
+        }
+
+        rendered
+    }
+
+    /// on-screen column the cursor sits at once tabs on its line are expanded
+    fn render_x(&self) -> usize {
+        let line = self.data.line(self.cur_row);
+        let mut x = 0;
+
+        for col in 0..self.cur_col.min(self.line_content_len(self.cur_row)) {
+            if line.char(col) == '\t' {
+                x += TAB_STOP - (x % TAB_STOP);
+            } else {
+                x += 1;
+            }
+        }
+
+        x
+    }
+
+    /// width of the line-number gutter: digit count of the last line plus one padding column
+    fn gutter_width(&self) -> u16 {
+        if !self.show_line_numbers {
+            return 0;
+        }
+
+        let digits = (self.num_lines() as f64).log10().floor() as usize + 1;
+        (digits + 1) as u16
+    }
+
+    /// right-aligns `data_row`'s 1-indexed line number into its gutter cells
+    fn draw_line_number(
+        &self,
+        window: &mut Window,
+        row: u16,
+        start_col: u16,
+        gutter_width: u16,
+        data_row: usize,
+    ) {
+        let digits_width = gutter_width as usize - 1;
+        let number = (data_row + 1).to_string();
+        let pad = digits_width.saturating_sub(number.len());
+
+        let mut col = start_col as usize;
+        for _ in 0..pad {
+            window[row as usize][col] = ' ';
+            col += 1;
+        }
+        for c in number.chars() {
+            window[row as usize][col] = c;
+            col += 1;
+        }
+        window[row as usize][col] = ' '; // padding column separating the gutter from the text
+    }
 }
 
 /// Cursor Movement
@@ -169,29 +470,40 @@ impl Buffer {
             self.buf_col += 1;
         }
 
-        // col
-        while self.cur_col < self.buf_row {
+        // col, kept in render-column space (tabs expanded) to match how
+        // buf_row indexes into render_line's output and col_in_frame
+        let render_x = self.render_x();
+
+        while render_x < self.buf_row {
             self.buf_row -= 1;
         }
 
-        while self.cur_col > self.buf_row + frame_width as usize - 1 {
+        while render_x > self.buf_row + frame_width as usize - 1 {
             self.buf_row += 1;
         }
     }
 
-    /// reset cursor x to point at min of current line len - 1 and previous cursor x
+    /// moves the cursor to `col`, the same way a horizontal move or an edit would,
+    /// updating `col_want` so vertical motion retargets this column
+    fn set_cur_col(&mut self, col: usize) {
+        self.cur_col = col;
+        self.col_want = col;
+    }
+
+    /// reset cursor x to point at min of current line len - 1 and `col_want`,
+    /// the column vertical motion is trying to reach (vim's sticky column)
     fn reset_x(&mut self) {
-        self.cur_col = self.cur_col.min(self.data[self.cur_row].len() - 1);
+        self.cur_col = self.col_want.min(self.line_len(self.cur_row) - 1);
     }
 
     /// shifts cursor to first col in current row
     fn move_start_of_line(&mut self) {
-        self.cur_col = 0;
+        self.set_cur_col(0);
     }
 
     /// shifts cursor to end col in current row
     fn move_end_of_line(&mut self) {
-        self.cur_col = self.data[self.cur_row].len() - 1;
+        self.set_cur_col(self.line_len(self.cur_row) - 1);
     }
 
     /// shifts cursor up a row
@@ -209,7 +521,7 @@ impl Buffer {
     /// shifts cursor down a row
     pub fn move_down(&mut self) -> bool {
         // if moved a line down return true
-        if self.cur_row < self.data.len() - 1 {
+        if self.cur_row < self.num_lines() - 1 {
             self.cur_row += 1;
             self.reset_x();
             return true;
@@ -220,8 +532,8 @@ impl Buffer {
 
     /// shifts cursor right a column
     pub fn move_right(&mut self) {
-        if self.cur_col < self.data[self.cur_row].len() - 1 {
-            self.cur_col += 1;
+        if self.cur_col < self.line_len(self.cur_row) - 1 {
+            self.set_cur_col(self.cur_col + 1);
         } else {
             if self.move_down() {
                 self.move_start_of_line();
@@ -232,43 +544,281 @@ impl Buffer {
     /// shifts cursor left a column
     pub fn move_left(&mut self) {
         if self.cur_col > 0 {
-            self.cur_col -= 1;
+            self.set_cur_col(self.cur_col - 1);
         } else {
             if self.move_up() {
                 self.move_end_of_line();
             }
         }
     }
+
+    /// char immediately following (row, col), wrapping to the next line's
+    /// first char once the line's '\0' delimiter is reached
+    fn next_pos(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        if col + 1 < self.line_len(row) {
+            Some((row, col + 1))
+        } else if row + 1 < self.num_lines() {
+            Some((row + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// char immediately preceding (row, col), wrapping to the previous line's
+    /// '\0' delimiter once col 0 is reached
+    fn prev_pos(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        if col > 0 {
+            Some((row, col - 1))
+        } else if row > 0 {
+            Some((row - 1, self.line_len(row - 1) - 1))
+        } else {
+            None
+        }
+    }
+
+    /// shifts cursor to the start of the next word (vim's `w`/`W`)
+    fn move_to_next_word_start(&mut self, classify: fn(char) -> CharClass) {
+        let (mut row, mut col) = (self.cur_row, self.cur_col);
+        let start_class = classify(self.char_at(row, col));
+
+        if start_class != CharClass::Space {
+            while let Some((r, c)) = self.next_pos(row, col) {
+                if classify(self.char_at(r, c)) != start_class {
+                    break;
+                }
+                (row, col) = (r, c);
+            }
+        }
+
+        while let Some((r, c)) = self.next_pos(row, col) {
+            (row, col) = (r, c);
+            if classify(self.char_at(r, c)) != CharClass::Space {
+                break;
+            }
+        }
+
+        self.cur_row = row;
+        self.set_cur_col(col);
+    }
+
+    /// shifts cursor to the start of the previous word (vim's `b`/`B`)
+    fn move_to_prev_word_start(&mut self, classify: fn(char) -> CharClass) {
+        let (mut row, mut col) = match self.prev_pos(self.cur_row, self.cur_col) {
+            Some(p) => p,
+            None => return,
+        };
+
+        while classify(self.char_at(row, col)) == CharClass::Space {
+            match self.prev_pos(row, col) {
+                Some((r, c)) => (row, col) = (r, c),
+                None => {
+                    self.cur_row = row;
+                    self.set_cur_col(col);
+                    return;
+                }
+            }
+        }
+
+        let class = classify(self.char_at(row, col));
+        while let Some((r, c)) = self.prev_pos(row, col) {
+            if classify(self.char_at(r, c)) != class {
+                break;
+            }
+            (row, col) = (r, c);
+        }
+
+        self.cur_row = row;
+        self.set_cur_col(col);
+    }
+
+    /// shifts cursor to the end of the next word (vim's `e`/`E`)
+    fn move_to_next_word_end(&mut self, classify: fn(char) -> CharClass) {
+        let (mut row, mut col) = match self.next_pos(self.cur_row, self.cur_col) {
+            Some(p) => p,
+            None => return,
+        };
+
+        while classify(self.char_at(row, col)) == CharClass::Space {
+            match self.next_pos(row, col) {
+                Some((r, c)) => (row, col) = (r, c),
+                None => {
+                    self.cur_row = row;
+                    self.set_cur_col(col);
+                    return;
+                }
+            }
+        }
+
+        let class = classify(self.char_at(row, col));
+        while let Some((r, c)) = self.next_pos(row, col) {
+            if classify(self.char_at(r, c)) != class {
+                break;
+            }
+            (row, col) = (r, c);
+        }
+
+        self.cur_row = row;
+        self.set_cur_col(col);
+    }
+
+    /// shifts cursor to the start of the next word
+    pub fn move_next_word_start(&mut self) {
+        self.move_to_next_word_start(classify);
+    }
+
+    /// shifts cursor to the start of the next WORD (whitespace-delimited)
+    pub fn move_next_big_word_start(&mut self) {
+        self.move_to_next_word_start(classify_big);
+    }
+
+    /// shifts cursor to the start of the previous word
+    pub fn move_prev_word_start(&mut self) {
+        self.move_to_prev_word_start(classify);
+    }
+
+    /// shifts cursor to the start of the previous WORD (whitespace-delimited)
+    pub fn move_prev_big_word_start(&mut self) {
+        self.move_to_prev_word_start(classify_big);
+    }
+
+    /// shifts cursor to the end of the next word
+    pub fn move_next_word_end(&mut self) {
+        self.move_to_next_word_end(classify);
+    }
+
+    /// shifts cursor to the end of the next WORD (whitespace-delimited)
+    pub fn move_next_big_word_end(&mut self) {
+        self.move_to_next_word_end(classify_big);
+    }
 }
 
 /// Getters
 impl Buffer {
+    /// cursor's column inside the frame, in tab-expanded render space so it
+    /// stays aligned with tabbed text on screen
     pub fn col_in_frame(&self) -> u16 {
-        (self.cur_col - self.buf_row) as u16
+        (self.render_x() - self.buf_row) as u16 + self.gutter_width()
     }
     pub fn row_in_frame(&self) -> u16 {
         (self.cur_row - self.buf_col) as u16
     }
+
+    /// current cursor position as (row, col)
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.cur_row, self.cur_col)
+    }
+
+    /// moves the cursor directly to (row, col), e.g. to restore a saved position
+    pub fn set_cursor(&mut self, row: usize, col: usize) {
+        self.cur_row = row;
+        self.set_cur_col(col);
+    }
 }
 
-/// Helper Function
-fn read_buffer(file_path: &str) -> Vec<Vec<char>> {
-    /* read each line of file separated by either '\n' || '\r'
-    and add '\0' at end of each line */
-    fs::read_to_string(&file_path)
-        .unwrap()
-        .split(|c| c == '\n' || c == '\r')
-        .map(|line| {
-            let mut line = line.chars().collect::<Vec<char>>();
-            line.push('\0');
-            line
-        })
-        .collect()
+/// Search
+impl Buffer {
+    /// moves the cursor to the nearest match for `query` starting from `from`,
+    /// wrapping at the end (or start) of the document; does not touch `last_query`
+    pub fn search_from(&mut self, query: &str, from: (usize, usize), forward: bool) -> bool {
+        match self.find_match(query, from, forward) {
+            Some((row, col)) => {
+                self.set_cursor(row, col);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// remembers `query` as the target for `search_next`/`search_prev`
+    pub fn confirm_search(&mut self, query: String) {
+        self.last_query = query;
+    }
+
+    /// jumps to the next occurrence of the last confirmed query (vim's `n`).
+    /// Rescans from the cursor on every call rather than walking a precomputed
+    /// match list, so edits made between searches can't leave it pointing at a
+    /// stale offset.
+    pub fn search_next(&mut self) {
+        if !self.last_query.is_empty() {
+            self.search_from(&self.last_query.clone(), self.cursor(), true);
+        }
+    }
+
+    /// jumps to the previous occurrence of the last confirmed query (vim's `N`)
+    pub fn search_prev(&mut self) {
+        if !self.last_query.is_empty() {
+            self.search_from(&self.last_query.clone(), self.cursor(), false);
+        }
+    }
+
+    /// finds the nearest (row, col) where `query` matches, scanning from `from`
+    /// and wrapping around the document
+    fn find_match(&self, query: &str, from: (usize, usize), forward: bool) -> Option<(usize, usize)> {
+        if query.is_empty() {
+            return None;
+        }
+
+        let total_chars = self.data.len_chars();
+        if total_chars == 0 {
+            return None;
+        }
+
+        let qchars: Vec<char> = query.chars().collect();
+        let start_idx = self.char_idx(from.0, from.1);
+
+        for offset in 1..=total_chars {
+            let idx = if forward {
+                (start_idx + offset) % total_chars
+            } else {
+                (start_idx + total_chars - offset) % total_chars
+            };
+
+            if self.matches_at(idx, &qchars) {
+                let row = self.data.char_to_line(idx);
+                let col = idx - self.data.line_to_char(row);
+                return Some((row, col));
+            }
+        }
+
+        None
+    }
+
+    /// whether `qchars` occurs starting at rope char index `idx`
+    fn matches_at(&self, idx: usize, qchars: &[char]) -> bool {
+        if idx + qchars.len() > self.data.len_chars() {
+            return false;
+        }
+        qchars
+            .iter()
+            .enumerate()
+            .all(|(i, &c)| self.data.char(idx + i) == c)
+    }
 }
 
-/// At least one empty line for buf_x and buf_y to point here x: 0, y: 0
-/// pointing at first line and last char which is just delimeter.
-/// This avoid index out of bound
-fn empty_buffer() -> Vec<Vec<char>> {
-    vec![vec!['\0']]
+/// Classification used by word-wise motions to find run boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Punct,
+    Space,
+}
+
+/// classifies for `w`/`b`/`e`: word-chars, punctuation and whitespace are distinct runs
+fn classify(c: char) -> CharClass {
+    if c == '\0' || c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+/// classifies for `W`/`B`/`E`: only whitespace separates runs
+fn classify_big(c: char) -> CharClass {
+    if c == '\0' || c.is_whitespace() {
+        CharClass::Space
+    } else {
+        CharClass::Word
+    }
 }
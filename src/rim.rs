@@ -1,6 +1,7 @@
 use std::{
+    collections::HashMap,
     io::{stdout, StdoutLock, Write},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::buffer::Buffer;
@@ -16,6 +17,9 @@ use crossterm::{
 const MAX_COLS: usize = 1000; // max chars in a line
 const MAX_ROWS: usize = 100; // max line
 
+/// how long the kilo-style "press ctrl-q again to quit" guard stays armed
+const QUIT_GUARD_WINDOW: Duration = Duration::from_secs(2);
+
 pub type Window = [Row; MAX_ROWS];
 pub type Row = [char; MAX_COLS];
 
@@ -39,12 +43,88 @@ impl Frame {
     }
 }
 
+/// Editing mode, vim-style: `Normal` interprets keys as commands, `Insert` types
+/// them, `Search` reads an incremental search query off the status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Normal,
+    Insert,
+    Search,
+}
+
+/// A command bound to a (mode, key) pair. Kept to `fn(&mut Buffer)` so entries
+/// can point straight at `Buffer` methods without any closure state.
+type KeyAction = fn(&mut Buffer);
+type KeyMap = HashMap<(Mode, event::KeyCode), KeyAction>;
+
+fn move_up(buf: &mut Buffer) {
+    buf.move_up();
+}
+
+fn move_down(buf: &mut Buffer) {
+    buf.move_down();
+}
+
+/// Builds the Normal-mode action table. Modeled on breed's action table: holds
+/// the Normal-mode commands that only act on the buffer. Ctrl chords, mode
+/// transitions, arrow keys and Insert-mode typing are matched in `process_key`.
+fn build_keymap() -> KeyMap {
+    let mut map: KeyMap = HashMap::new();
+    map.insert((Mode::Normal, event::KeyCode::Char('h')), Buffer::move_left);
+    map.insert((Mode::Normal, event::KeyCode::Char('l')), Buffer::move_right);
+    map.insert((Mode::Normal, event::KeyCode::Char('j')), move_down);
+    map.insert((Mode::Normal, event::KeyCode::Char('k')), move_up);
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('x')),
+        Buffer::delete_under_cursor,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('w')),
+        Buffer::move_next_word_start,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('b')),
+        Buffer::move_prev_word_start,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('e')),
+        Buffer::move_next_word_end,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('W')),
+        Buffer::move_next_big_word_start,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('B')),
+        Buffer::move_prev_big_word_start,
+    );
+    map.insert(
+        (Mode::Normal, event::KeyCode::Char('E')),
+        Buffer::move_next_big_word_end,
+    );
+    map.insert((Mode::Normal, event::KeyCode::Char('u')), Buffer::undo);
+    map.insert((Mode::Normal, event::KeyCode::Char('n')), Buffer::search_next);
+    map.insert((Mode::Normal, event::KeyCode::Char('N')), Buffer::search_prev);
+    map
+}
+
 pub struct Rim<'a> {
     buf: Buffer,
+    mode: Mode,
+    keymap: KeyMap,
     window: Window,
     window_width: u16,
     window_height: u16,
     exit: bool,
+    /// deadline until which a second Ctrl-q actually quits, armed by the first
+    /// Ctrl-q press while the buffer has unsaved changes
+    quit_guard: Option<Instant>,
+    /// transient message shown on the status line instead of the default summary
+    status_message: Option<String>,
+    /// query typed so far in `Mode::Search`
+    search_query: String,
+    /// cursor position when `/` was pressed, restored if the search is cancelled
+    search_origin: (usize, usize),
     stdout: StdoutLock<'a>,
 }
 
@@ -54,10 +134,16 @@ impl<'a> Rim<'a> {
 
         Self {
             buf: Buffer::new(Some(file_path.to_string())),
+            mode: Mode::Normal,
+            keymap: build_keymap(),
             window: [[' '; MAX_COLS]; MAX_ROWS],
             window_width: terminal_size.0,
             window_height: terminal_size.1,
             exit: false,
+            quit_guard: None,
+            status_message: None,
+            search_query: String::new(),
+            search_origin: (0, 0),
             stdout: stdout().lock(),
         }
     }
@@ -66,28 +152,114 @@ impl<'a> Rim<'a> {
         enable_raw_mode().unwrap();
     }
 
+    /// Ctrl-q: quits immediately if the buffer is clean, otherwise requires a
+    /// second press within `QUIT_GUARD_WINDOW` (kilo's unsaved-changes guard)
+    fn quit(&mut self) {
+        if !self.buf.is_dirty() {
+            self.exit = true;
+            return;
+        }
+
+        let guard_armed = self.quit_guard.is_some_and(|deadline| Instant::now() < deadline);
+        if guard_armed {
+            self.exit = true;
+        } else {
+            self.quit_guard = Some(Instant::now() + QUIT_GUARD_WINDOW);
+            self.status_message = Some("unsaved changes, press Ctrl-q again to quit".to_string());
+        }
+    }
+
+    /// re-searches the live query from the pre-search cursor and updates the status line
+    fn update_search(&mut self) {
+        self.buf.set_cursor(self.search_origin.0, self.search_origin.1);
+        self.buf.search_from(&self.search_query, self.search_origin, true);
+        self.status_message = Some(format!("/{}", self.search_query));
+    }
+
     fn process_key(&mut self, key_event: KeyEvent) {
+        self.status_message = None;
+
         // NOTE: KeyModifiers are bitfields
         // if only control is pressed [among keymodifiers {SHIFT, CAPSLOCK, etc}]
         if key_event.modifiers == KeyModifiers::CONTROL {
             match key_event.code {
                 // ctrl + s => for save
                 event::KeyCode::Char('s') => self.buf.save(),
-                event::KeyCode::Char('q') => self.exit = true,
+                event::KeyCode::Char('q') => self.quit(),
+                event::KeyCode::Char('r') => self.buf.redo(),
+                // ctrl + n => toggle line-number gutter
+                event::KeyCode::Char('n') => self.buf.toggle_line_numbers(),
                 _ => {}
             }
-        } else {
-            // NOTE: on Shift + char, Char is also Uppercase, hence no extra work for it
-            match key_event.code {
-                event::KeyCode::Backspace => self.buf.delete_char(),
-                event::KeyCode::Enter => self.buf.insert_nl(),
-                event::KeyCode::Left => self.buf.move_left(),
-                event::KeyCode::Right => self.buf.move_right(),
-                event::KeyCode::Up => _ = self.buf.move_up(),
-                event::KeyCode::Down => _ = self.buf.move_down(),
-                event::KeyCode::Char(c) => self.buf.insert_char(c),
-                _ => {}
+            return;
+        }
+
+        // mode transitions live outside the keymap: they mutate `self.mode`,
+        // not the buffer, so they can't be `fn(&mut Buffer)` entries
+        match (self.mode, key_event.code) {
+            (Mode::Normal, event::KeyCode::Char('i')) => {
+                self.mode = Mode::Insert;
+                return;
+            }
+            (Mode::Normal, event::KeyCode::Char('a')) => {
+                self.buf.move_right();
+                self.mode = Mode::Insert;
+                return;
+            }
+            (Mode::Normal, event::KeyCode::Char('o')) => {
+                self.buf.open_line_below();
+                self.mode = Mode::Insert;
+                return;
+            }
+            (Mode::Insert, event::KeyCode::Esc) => {
+                self.mode = Mode::Normal;
+                return;
+            }
+            (Mode::Normal, event::KeyCode::Char('/')) => {
+                self.search_origin = self.buf.cursor();
+                self.search_query.clear();
+                self.mode = Mode::Search;
+                self.status_message = Some("/".to_string());
+                return;
+            }
+            (Mode::Search, event::KeyCode::Esc) => {
+                self.buf.set_cursor(self.search_origin.0, self.search_origin.1);
+                self.mode = Mode::Normal;
+                return;
+            }
+            (Mode::Search, event::KeyCode::Enter) => {
+                self.buf.confirm_search(self.search_query.clone());
+                self.mode = Mode::Normal;
+                return;
+            }
+            (Mode::Search, event::KeyCode::Backspace) => {
+                self.search_query.pop();
+                self.update_search();
+                return;
             }
+            (Mode::Search, event::KeyCode::Char(c)) => {
+                self.search_query.push(c);
+                self.update_search();
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(action) = self.keymap.get(&(self.mode, key_event.code)) {
+            action(&mut self.buf);
+            return;
+        }
+
+        // keys shared by every mode, plus Insert-mode typing
+        match key_event.code {
+            event::KeyCode::Left => self.buf.move_left(),
+            event::KeyCode::Right => self.buf.move_right(),
+            event::KeyCode::Up => _ = self.buf.move_up(),
+            event::KeyCode::Down => _ = self.buf.move_down(),
+            event::KeyCode::Backspace if self.mode == Mode::Insert => self.buf.delete_char(),
+            event::KeyCode::Enter if self.mode == Mode::Insert => self.buf.insert_nl(),
+            event::KeyCode::Char(c) if self.mode == Mode::Insert => self.buf.insert_char(c),
+            _ => {}
         }
     }
 
@@ -124,10 +296,13 @@ impl<'a> Rim<'a> {
     }
 
     fn refresh_screen(&mut self) -> std::io::Result<()> {
-        let frame = Some(Frame::new(0, 0, self.window_height, self.window_width));
+        // reserve the bottom row of the window for the status line
+        let edit_height = self.window_height.saturating_sub(1);
+        let frame = Some(Frame::new(0, 0, edit_height, self.window_width));
 
         if let Some(frame) = frame {
             self.buf.print(&mut self.window, frame)?;
+            self.draw_status_line(edit_height);
 
             self.stdout.queue(cursor::MoveTo(0, 0))?;
             self.stdout.queue(cursor::Hide)?;
@@ -149,6 +324,24 @@ impl<'a> Rim<'a> {
         Ok(())
     }
 
+    /// draws the status message (or the buffer's default summary) into `row`
+    fn draw_status_line(&mut self, row: u16) {
+        let status = self
+            .status_message
+            .clone()
+            .unwrap_or_else(|| self.buf.status_line());
+
+        let mut col = 0;
+        for c in status.chars().take(self.window_width as usize) {
+            self.window[row as usize][col] = c;
+            col += 1;
+        }
+        while col < self.window_width as usize {
+            self.window[row as usize][col] = ' ';
+            col += 1;
+        }
+    }
+
     fn exit(&mut self) {
         // on exit
         self.stdout